@@ -1,96 +1,175 @@
 #![cfg_attr(target_os = "none", no_std)]
 use core::convert::TryInto;
-pub struct Register {
+use core::marker::PhantomData;
+
+/// Marker type for a register that can only be read.
+pub struct ReadOnly;
+/// Marker type for a register that can only be written.
+pub struct WriteOnly;
+/// Marker type for a register that can be both read and written.
+pub struct ReadWrite;
+
+/// Implemented by access markers that allow `r`/`rf`.
+pub trait Readable {}
+/// Implemented by access markers that allow `w`/`wf`.
+pub trait Writable {}
+
+impl Readable for ReadOnly {}
+impl Readable for ReadWrite {}
+impl Writable for WriteOnly {}
+impl Writable for ReadWrite {}
+
+/// `Peripheral` is a zero-sized tag (one per peripheral block, stamped by
+/// `svd2utra` on every one of that peripheral's registers and fields) so a
+/// `Register`/`Field` from one peripheral can't be used against another
+/// peripheral's `CSR`, even if the two happen to share a register offset.
+pub struct Register<Peripheral, Access = ReadWrite> {
     /// Offset of this register within this CSR
     offset: usize,
+
+    _peripheral: PhantomData<Peripheral>,
+    _access: PhantomData<Access>,
+}
+
+impl<Peripheral> Register<Peripheral, ReadWrite> {
+    pub const fn new(offset: usize) -> Self {
+        Register {
+            offset,
+            _peripheral: PhantomData,
+            _access: PhantomData,
+        }
+    }
+}
+
+impl<Peripheral> Register<Peripheral, ReadOnly> {
+    pub const fn new_ro(offset: usize) -> Self {
+        Register {
+            offset,
+            _peripheral: PhantomData,
+            _access: PhantomData,
+        }
+    }
+}
+
+impl<Peripheral> Register<Peripheral, WriteOnly> {
+    pub const fn new_wo(offset: usize) -> Self {
+        Register {
+            offset,
+            _peripheral: PhantomData,
+            _access: PhantomData,
+        }
+    }
 }
 
-impl Register {
-    pub const fn new(offset: usize) -> Register {
-        Register { offset }
+// Written by hand rather than `#[derive(Clone, Copy)]`, which would also
+// require `Peripheral: Copy` and `Access: Copy` even though both only ever
+// appear behind `PhantomData`.
+impl<Peripheral, Access> Clone for Register<Peripheral, Access> {
+    fn clone(&self) -> Self {
+        *self
     }
 }
+impl<Peripheral, Access> Copy for Register<Peripheral, Access> {}
 
-pub struct Field {
-    /// A bitmask we use to AND to the value, unshifted.
-    /// E.g. for a width of `3` bits, this mask would be 0b111.
-    mask: usize,
+/// Bitmask for a field of the given width, unshifted (e.g. width `3` gives
+/// `0b111`). A width covering the full word yields `usize::MAX` rather than
+/// overflowing the `1 << WIDTH` shift.
+const fn mask_for_width(width: usize) -> usize {
+    if width >= usize::BITS as usize {
+        usize::MAX
+    } else {
+        (1usize << width) - 1
+    }
+}
 
+/// `WIDTH` is a const generic rather than a runtime field: the compiler
+/// rejects a field that doesn't fit in the register at the call site, and
+/// the mask is folded to a constant instead of going through a width match
+/// table at runtime.
+pub struct Field<const WIDTH: usize, Peripheral, Access = ReadWrite> {
     /// Offset of the first bit in this field
     offset: usize,
 
     /// A copy of the register address that this field
     /// is a member of. Ideally this is optimized out by the
     /// compiler.
-    register: Register,
-}
-
-impl Field {
-    /// Define a new CSR field with the given width at a specified
-    /// offset from the start of the register.
-    pub const fn new(width: usize, offset: usize, register: Register) -> Field {
-        // Asserts don't work in const fn yet.
-        // assert!(width != 0, "field width cannot be 0");
-        // assert!((width + offset) < 32, "field with and offset must fit within a 32-bit value");
-
-        // It would be lovely if we could call `usize::pow()` in a const fn.
-        let mask = match width {
-            0 => 0,
-            1 => 1,
-            2 => 3,
-            3 => 7,
-            4 => 15,
-            5 => 31,
-            6 => 63,
-            7 => 127,
-            8 => 255,
-            9 => 511,
-            10 => 1023,
-            11 => 2047,
-            12 => 4095,
-            13 => 8191,
-            14 => 16383,
-            15 => 32767,
-            16 => 65535,
-            17 => 131071,
-            18 => 262143,
-            19 => 524287,
-            20 => 1048575,
-            21 => 2097151,
-            22 => 4194303,
-            23 => 8388607,
-            24 => 16777215,
-            25 => 33554431,
-            26 => 67108863,
-            27 => 134217727,
-            28 => 268435455,
-            29 => 536870911,
-            30 => 1073741823,
-            31 => 2147483647,
-            _ => 0,
-        };
-        Field {
-            mask,
-            offset,
-            register,
-        }
+    register: Register<Peripheral, Access>,
+}
+
+impl<const WIDTH: usize, Peripheral, Access> Field<WIDTH, Peripheral, Access> {
+    /// Define a new CSR field of `WIDTH` bits at a specified offset from the
+    /// start of the register.
+    pub const fn new(offset: usize, register: Register<Peripheral, Access>) -> Self {
+        assert!(WIDTH != 0, "field width cannot be 0");
+        assert!(
+            WIDTH + offset <= usize::BITS as usize,
+            "field width and offset must fit within a register-sized value"
+        );
+        Field { offset, register }
+    }
+}
+
+// See the matching note on `Register`'s `Clone`/`Copy` impls above.
+impl<const WIDTH: usize, Peripheral, Access> Clone for Field<WIDTH, Peripheral, Access> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<const WIDTH: usize, Peripheral, Access> Copy for Field<WIDTH, Peripheral, Access> {}
+
+/// Accumulates a sequence of field edits against a single register-sized
+/// value so they can be committed with one `write_volatile`. Tagged with the
+/// same `Peripheral` as the `CSR` that produced it, so `set()` is rejected
+/// at compile time for a field belonging to a different peripheral.
+pub struct Writer<T, Peripheral> {
+    value: usize,
+    _marker: PhantomData<T>,
+    _peripheral: PhantomData<Peripheral>,
+}
+
+impl<T, Peripheral> Writer<T, Peripheral>
+where
+    T: core::convert::TryFrom<usize> + core::convert::TryInto<usize> + core::default::Default,
+{
+    /// Clear `field`'s bits and OR in `value`, staying within the field's
+    /// mask so this edit can't bleed into neighboring fields. `value` is
+    /// masked to `WIDTH` bits first, so a value wider than the field
+    /// truncates cleanly instead of corrupting its neighbors.
+    pub fn set<const WIDTH: usize, Access>(
+        self,
+        field: Field<WIDTH, Peripheral, Access>,
+        value: T,
+    ) -> Self {
+        let value_as_usize: usize = value.try_into().unwrap_or_default();
+        self.set_raw(mask_for_width(WIDTH), field.offset, value_as_usize)
+    }
+
+    /// Core of `set()`, shared with `CSR::set_range()` for bit ranges that
+    /// don't have a pre-declared `Field` constant.
+    fn set_raw(mut self, mask: usize, offset: usize, value_as_usize: usize) -> Self {
+        self.value = (self.value & !(mask << offset)) | ((value_as_usize & mask) << offset);
+        self
     }
 }
 
-pub struct CSR<T> {
+pub struct CSR<T, Peripheral> {
     base: *mut T,
+    _peripheral: PhantomData<Peripheral>,
 }
 
-impl<T> CSR<T>
+impl<T, Peripheral> CSR<T, Peripheral>
 where
     T: core::convert::TryFrom<usize> + core::convert::TryInto<usize> + core::default::Default,
 {
     pub fn new(base: *mut T) -> Self {
-        CSR { base }
+        CSR {
+            base,
+            _peripheral: PhantomData,
+        }
     }
 
     /// Read the contents of this register
-    pub fn r(&mut self, reg: Register) -> T {
+    pub fn r<Access: Readable>(&mut self, reg: Register<Peripheral, Access>) -> T {
         let usize_base: *mut usize = unsafe { core::mem::transmute(self.base) };
         unsafe { usize_base.add(reg.offset).read_volatile() }
             .try_into()
@@ -98,20 +177,28 @@ where
     }
 
     /// Read a field from this CSR
-    pub fn rf(&mut self, field: Field) -> T {
+    pub fn rf<const WIDTH: usize, Access: Readable>(
+        &mut self,
+        field: Field<WIDTH, Peripheral, Access>,
+    ) -> T {
         let usize_base: *mut usize = unsafe { core::mem::transmute(self.base) };
         ((unsafe { usize_base.add(field.register.offset).read_volatile() } >> field.offset)
-            & field.mask)
+            & mask_for_width(WIDTH))
             .try_into()
             .unwrap_or_default()
     }
 
     /// Read-modify-write a given field in this CSR
-    pub fn rmwf(&mut self, field: Field, value: T) {
+    pub fn rmwf<const WIDTH: usize, Access: Readable + Writable>(
+        &mut self,
+        field: Field<WIDTH, Peripheral, Access>,
+        value: T,
+    ) {
         let usize_base: *mut usize = unsafe { core::mem::transmute(self.base) };
-        let value_as_usize: usize = value.try_into().unwrap_or_default() << field.offset;
+        let mask = mask_for_width(WIDTH);
+        let value_as_usize: usize = (value.try_into().unwrap_or_default() & mask) << field.offset;
         let previous =
-            unsafe { usize_base.add(field.register.offset).read_volatile() } & !field.mask;
+            unsafe { usize_base.add(field.register.offset).read_volatile() } & !(mask << field.offset);
         unsafe {
             usize_base
                 .add(field.register.offset)
@@ -120,9 +207,14 @@ where
     }
 
     /// Write a given field without reading it first
-    pub fn wf(&mut self, field: Field, value: T) {
+    pub fn wf<const WIDTH: usize, Access: Writable>(
+        &mut self,
+        field: Field<WIDTH, Peripheral, Access>,
+        value: T,
+    ) {
         let usize_base: *mut usize = unsafe { core::mem::transmute(self.base) };
-        let value_as_usize: usize = value.try_into().unwrap_or_default() << field.offset;
+        let mask = mask_for_width(WIDTH);
+        let value_as_usize: usize = (value.try_into().unwrap_or_default() & mask) << field.offset;
         unsafe {
             usize_base
                 .add(field.register.offset)
@@ -131,27 +223,294 @@ where
     }
 
     /// Write the entire contents of a register without reading it first
-    pub fn w(&mut self, reg: Register, value: T) {
+    pub fn w<Access: Writable>(&mut self, reg: Register<Peripheral, Access>, value: T) {
         let usize_base: *mut usize = unsafe { core::mem::transmute(self.base) };
         let value_as_usize: usize = value.try_into().unwrap_or_default();
         unsafe { usize_base.add(reg.offset).write_volatile(value_as_usize) };
     }
+
+    /// Read-modify-write several fields of `reg` with a single
+    /// `read_volatile`/`write_volatile` pair instead of one RMW per field.
+    /// `f` receives a `Writer` seeded with the register's current contents
+    /// and chains `set()` calls to stage each field's new value:
+    ///
+    /// ```ignore
+    /// csr.modify(RX_CTL, |w| w.set(RX_CTL_ENABLE, 1).set(RX_CTL_RESET, 0));
+    /// ```
+    ///
+    /// `w.set()` is tagged with the same `Peripheral` as `self`, so a field
+    /// belonging to a different peripheral is rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// use utra::{CSR, Register, Field};
+    ///
+    /// struct Audio;
+    /// struct Uart;
+    ///
+    /// let audio_ctl: Register<Audio> = Register::new(0x0c);
+    /// let uart_ctl: Register<Uart> = Register::new(0x00);
+    /// let uart_field: Field<1, Uart> = Field::new(0, uart_ctl);
+    ///
+    /// let mut audio = CSR::<u32, Audio>::new(0x1000_0000 as *mut u32);
+    /// audio.modify(audio_ctl, |w| w.set(uart_field, 1));
+    /// ```
+    pub fn modify<Access: Readable + Writable, F>(&mut self, reg: Register<Peripheral, Access>, f: F)
+    where
+        F: FnOnce(Writer<T, Peripheral>) -> Writer<T, Peripheral>,
+    {
+        let usize_base: *mut usize = unsafe { core::mem::transmute(self.base) };
+        let current = unsafe { usize_base.add(reg.offset).read_volatile() };
+        let writer = Writer {
+            value: current,
+            _marker: PhantomData,
+            _peripheral: PhantomData,
+        };
+        let result = f(writer);
+        unsafe { usize_base.add(reg.offset).write_volatile(result.value) };
+    }
+
+    /// Pulse a one-bit field: write a 1, then a 0, the classic reset/strobe
+    /// pattern for peripherals whose reset line must be asserted and then
+    /// deasserted. Goes through the same `modify` core as every other RMW
+    /// here, so it composes with the access-type and peripheral tagging.
+    pub fn toggle<Access: Readable + Writable>(&mut self, field: Field<1, Peripheral, Access>) {
+        let one = T::try_from(1).unwrap_or_default();
+        let zero = T::try_from(0).unwrap_or_default();
+        self.modify(field.register, |w| w.set(field, one));
+        self.modify(field.register, |w| w.set(field, zero));
+    }
+
+    /// Read-modify-write an inclusive bit range of `reg` in one pass, for
+    /// multi-bit fields (e.g. a clock divisor) that weren't worth a
+    /// pre-declared `Field` constant. Builds the mask from `range` at the
+    /// call site and goes through the same `modify` core `set()` does.
+    pub fn set_range<Access: Readable + Writable>(
+        &mut self,
+        reg: Register<Peripheral, Access>,
+        range: core::ops::RangeInclusive<usize>,
+        value: T,
+    ) {
+        let offset = *range.start();
+        assert!(
+            *range.end() < usize::BITS as usize,
+            "set_range: range must fit within a register-sized value"
+        );
+        let width = *range.end() - offset + 1;
+        let mask = mask_for_width(width);
+        let value_as_usize: usize = value.try_into().unwrap_or_default();
+        self.modify(reg, |w| w.set_raw(mask, offset, value_as_usize));
+    }
+
+    /// Read-modify-write `field` atomically with respect to an interrupt
+    /// handler or another hart touching the same register, instead of the
+    /// plain `read_volatile`/`write_volatile` pair `rmwf` uses.
+    ///
+    /// On `riscv32` with the `atomic` feature enabled this retries an
+    /// `lr.w`/`sc.w` loop until the store reservation holds; everywhere else
+    /// it falls back to a `critical-section`-guarded region.
+    pub fn rmwf_atomic<const WIDTH: usize, Access: Readable + Writable>(
+        &mut self,
+        field: Field<WIDTH, Peripheral, Access>,
+        value: T,
+    ) {
+        #[cfg(all(feature = "atomic", target_arch = "riscv32"))]
+        {
+            let usize_base: *mut usize = unsafe { core::mem::transmute(self.base) };
+            let value_as_usize: usize = value.try_into().unwrap_or_default();
+            unsafe {
+                atomic_riscv::rmw_word(
+                    usize_base.add(field.register.offset),
+                    mask_for_width(WIDTH),
+                    field.offset,
+                    value_as_usize,
+                )
+            };
+        }
+        #[cfg(not(all(feature = "atomic", target_arch = "riscv32")))]
+        {
+            critical_section::with(|_| self.rmwf(field, value));
+        }
+    }
+
+    /// `modify`'s atomic counterpart: the whole read-edit-write sequence
+    /// runs inside the same atomic/critical-section window as
+    /// [`rmwf_atomic`], so none of the fields staged by `f` can be lost to a
+    /// concurrent update.
+    pub fn modify_atomic<Access: Readable + Writable, F>(&mut self, reg: Register<Peripheral, Access>, mut f: F)
+    where
+        F: FnMut(Writer<T, Peripheral>) -> Writer<T, Peripheral>,
+    {
+        #[cfg(all(feature = "atomic", target_arch = "riscv32"))]
+        {
+            let usize_base: *mut usize = unsafe { core::mem::transmute(self.base) };
+            loop {
+                let addr = unsafe { usize_base.add(reg.offset) };
+                let current = unsafe { atomic_riscv::read_reservation_word(addr) };
+                let writer = Writer {
+                    value: current,
+                    _marker: PhantomData,
+                    _peripheral: PhantomData,
+                };
+                // `f` may run more than once if the store-conditional
+                // below loses its reservation, so it must be `FnMut`.
+                let result = f(writer);
+                if unsafe { atomic_riscv::write_conditional_word(addr, result.value) } {
+                    break;
+                }
+            }
+        }
+        #[cfg(not(all(feature = "atomic", target_arch = "riscv32")))]
+        {
+            critical_section::with(|_| self.modify(reg, &mut f));
+        }
+    }
+}
+
+/// LR/SC-based atomics for `riscv32`, gated behind the `atomic` feature for
+/// `no_std` callers who can't afford a `critical-section` implementation.
+#[cfg(all(feature = "atomic", target_arch = "riscv32"))]
+mod atomic_riscv {
+    use core::arch::asm;
+
+    /// Atomically clear `mask << offset` and OR in `value << offset`,
+    /// retrying the load-reserved/store-conditional pair until the store
+    /// succeeds so a concurrent interrupt or hart can't clobber the update.
+    pub unsafe fn rmw_word(addr: *mut usize, mask: usize, offset: usize, value: usize) {
+        let clear = !(mask << offset);
+        let set = (value & mask) << offset;
+        loop {
+            let mut tmp: usize;
+            let mut failed: usize;
+            asm!(
+                "lr.w {tmp}, ({addr})",
+                "and {tmp}, {tmp}, {clear}",
+                "or {tmp}, {tmp}, {set}",
+                "sc.w {failed}, {tmp}, ({addr})",
+                addr = in(reg) addr,
+                clear = in(reg) clear,
+                set = in(reg) set,
+                tmp = out(reg) tmp,
+                failed = out(reg) failed,
+            );
+            if failed == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Start a load-reserved on `addr`'s word, so a following
+    /// [`write_conditional_word`] can detect whether anything else wrote to it
+    /// in between.
+    pub unsafe fn read_reservation_word(addr: *mut usize) -> usize {
+        let value: usize;
+        asm!("lr.w {value}, ({addr})", addr = in(reg) addr, value = out(reg) value);
+        value
+    }
+
+    /// Complete the load-reserved/store-conditional pair started by
+    /// [`read_reservation_word`]. Returns `true` if the store held.
+    pub unsafe fn write_conditional_word(addr: *mut usize, value: usize) -> bool {
+        let failed: usize;
+        asm!("sc.w {failed}, {value}, ({addr})", addr = in(reg) addr, value = in(reg) value, failed = out(reg) failed);
+        failed == 0
+    }
+}
+
+/// A `CSR` whose multi-field read-modify-write operations run through the
+/// atomic path, for control registers shared between thread and interrupt
+/// context (or between harts).
+pub struct AtomicCsr<T, Peripheral> {
+    csr: CSR<T, Peripheral>,
+}
+
+impl<T, Peripheral> AtomicCsr<T, Peripheral>
+where
+    T: core::convert::TryFrom<usize> + core::convert::TryInto<usize> + core::default::Default,
+{
+    pub fn new(base: *mut T) -> Self {
+        AtomicCsr {
+            csr: CSR::new(base),
+        }
+    }
+
+    /// Read the contents of this register
+    pub fn r<Access: Readable>(&mut self, reg: Register<Peripheral, Access>) -> T {
+        self.csr.r(reg)
+    }
+
+    /// Read a field from this CSR
+    pub fn rf<const WIDTH: usize, Access: Readable>(
+        &mut self,
+        field: Field<WIDTH, Peripheral, Access>,
+    ) -> T {
+        self.csr.rf(field)
+    }
+
+    /// Atomically read-modify-write a given field in this CSR
+    pub fn rmwf<const WIDTH: usize, Access: Readable + Writable>(
+        &mut self,
+        field: Field<WIDTH, Peripheral, Access>,
+        value: T,
+    ) {
+        self.csr.rmwf_atomic(field, value)
+    }
+
+    /// Write a given field without reading it first
+    pub fn wf<const WIDTH: usize, Access: Writable>(
+        &mut self,
+        field: Field<WIDTH, Peripheral, Access>,
+        value: T,
+    ) {
+        self.csr.wf(field, value)
+    }
+
+    /// Write the entire contents of a register without reading it first
+    pub fn w<Access: Writable>(&mut self, reg: Register<Peripheral, Access>, value: T) {
+        self.csr.w(reg, value)
+    }
+
+    /// Atomically read-modify-write several fields of `reg`; see
+    /// [`CSR::modify_atomic`].
+    pub fn modify<Access: Readable + Writable, F>(&mut self, reg: Register<Peripheral, Access>, f: F)
+    where
+        F: FnMut(Writer<T, Peripheral>) -> Writer<T, Peripheral>,
+    {
+        self.csr.modify_atomic(reg, f)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     pub mod pac {
         pub mod audio {
-            pub const RX_CTL: crate::Register = crate::Register::new(0x0c);
-            pub const RX_CTL_ENABLE: crate::Field = crate::Field::new(1, 0, RX_CTL);
-            pub const RX_CTL_RESET: crate::Field = crate::Field::new(1, 1, RX_CTL);
+            /// Zero-sized tag stamped on every register/field belonging to
+            /// the audio peripheral, so they can't be used on another
+            /// peripheral's `CSR`.
+            pub struct Audio;
+
+            pub const RX_CTL: crate::Register<Audio> = crate::Register::new(0x0c);
+            pub const RX_CTL_ENABLE: crate::Field<1, Audio> = crate::Field::new(0, RX_CTL);
+            pub const RX_CTL_RESET: crate::Field<1, Audio> = crate::Field::new(1, RX_CTL);
         }
         pub mod uart {
-            pub const RXTX: crate::Register = crate::Register::new(0x00);
-            pub const RXTX_RXTX: crate::Field = crate::Field::new(8, 0, RXTX);
+            /// Zero-sized tag stamped on every register/field belonging to
+            /// the uart peripheral, so they can't be used on another
+            /// peripheral's `CSR`.
+            pub struct Uart;
+
+            pub const RXTX: crate::Register<Uart> = crate::Register::new(0x00);
+            pub const RXTX_RXTX: crate::Field<8, Uart> = crate::Field::new(0, RXTX);
 
-            pub const TXFULL: crate::Register = crate::Register::new(0x04);
-            pub const TXFULL_TXFULL: crate::Field = crate::Field::new(1, 0, TXFULL);
+            pub const TXFULL: crate::Register<Uart> = crate::Register::new(0x04);
+            pub const TXFULL_TXFULL: crate::Field<1, Uart> = crate::Field::new(0, TXFULL);
+
+            // EV_PENDING is a hardware status flag: only ever read, never
+            // written. `new_ro` makes a stray `.wf()` on it a compile error
+            // instead of a silent no-op write to a read-only register.
+            pub const EV_PENDING: crate::Register<Uart, crate::ReadOnly> =
+                crate::Register::new_ro(0x08);
+            pub const EV_PENDING_RX: crate::Field<1, Uart, crate::ReadOnly> =
+                crate::Field::new(0, EV_PENDING);
         }
     }
     #[test]
@@ -161,7 +520,8 @@ mod tests {
         // Audio tests
 
         // The audio block is a pointer to *mut 32.
-        let mut audio = CSR::new(0x1000_0000 as *mut u32);
+        let mut regs = [0usize; 16];
+        let mut audio = CSR::<u32, pac::audio::Audio>::new(regs.as_mut_ptr() as *mut u32);
 
         // Read the entire contents of the RX_CTL register
         audio.r(pac::audio::RX_CTL);
@@ -175,23 +535,107 @@ mod tests {
         // UART tests
 
         // Create the UART register as a pointer to *mut u8
-        let mut uart = CSR::new(0x1001_0000 as *mut u8);
+        let mut uart_regs = [0usize; 16];
+        let mut uart = CSR::<u8, pac::uart::Uart>::new(uart_regs.as_mut_ptr() as *mut u8);
 
-        // Write the RXTX field of the RXTX register
+        // Write the RXTX field of the RXTX register.
         uart.wf(pac::uart::RXTX_RXTX, b'a');
 
         // Or you can write the whole UART register
         uart.w(pac::uart::RXTX, b'a');
         assert_ne!(uart.rf(pac::uart::TXFULL_TXFULL), 1);
 
-        // Anomalies
+        // EV_PENDING is read-only, so reading it is fine...
+        uart.rf(pac::uart::EV_PENDING_RX);
+        // ...but `uart.wf(pac::uart::EV_PENDING_RX, 1)` no longer compiles:
+        // `ReadOnly` doesn't implement `Writable`.
+
+        // Former anomalies, now compile errors:
+        //
+        //   audio.wf(pac::uart::RXTX_RXTX, b'a' as _);
+        //   audio.wf(pac::uart::TXFULL_TXFULL, 1);
+        //
+        // `audio` is a `CSR<u32, pac::audio::Audio>`, and `RXTX_RXTX`/
+        // `TXFULL_TXFULL` are `Field<_, pac::uart::Uart, _>` — the
+        // peripheral tags don't match, so these no longer typecheck at all.
+    }
+
+    #[test]
+    fn modify_batches_multiple_fields() {
+        use super::*;
+
+        let mut regs = [0usize; 16];
+        let mut audio = CSR::<u32, pac::audio::Audio>::new(regs.as_mut_ptr() as *mut u32);
+        audio.modify(pac::audio::RX_CTL, |w| {
+            w.set(pac::audio::RX_CTL_ENABLE, 1)
+                .set(pac::audio::RX_CTL_RESET, 0)
+        });
+        assert_eq!(audio.rf(pac::audio::RX_CTL_ENABLE), 1);
+        assert_eq!(audio.rf(pac::audio::RX_CTL_RESET), 0);
+    }
+
+    #[test]
+    fn wf_truncates_oversized_value() {
+        use super::*;
+
+        let mut regs = [0usize; 16];
+        let mut uart = CSR::<u32, pac::uart::Uart>::new(regs.as_mut_ptr() as *mut u32);
+        // RXTX_RXTX is only 8 bits wide; bit 8 and above must not leak into
+        // the neighboring bits of RXTX.
+        uart.wf(pac::uart::RXTX_RXTX, 0x1_ff);
+        assert_eq!(uart.r(pac::uart::RXTX), 0xff);
+    }
+
+    #[test]
+    fn atomic_csr_rmwf_matches_plain_rmwf() {
+        use super::*;
 
-        // This compiles but requires a cast since `audio` is a pointer to
-        // u32, whereas `uart` is a pointer to u8.
-        audio.wf(pac::uart::RXTX_RXTX, b'a' as _);
+        let mut regs = [0usize; 16];
+        let mut audio = AtomicCsr::<u32, pac::audio::Audio>::new(regs.as_mut_ptr() as *mut u32);
+        audio.rmwf(pac::audio::RX_CTL_ENABLE, 1);
+        assert_eq!(audio.rf(pac::audio::RX_CTL_ENABLE), 1);
+
+        audio.modify(pac::audio::RX_CTL, |w| w.set(pac::audio::RX_CTL_RESET, 1));
+        assert_eq!(audio.rf(pac::audio::RX_CTL_RESET), 1);
+        // ENABLE set above must survive the later, independent modify().
+        assert_eq!(audio.rf(pac::audio::RX_CTL_ENABLE), 1);
+    }
+
+    #[test]
+    fn toggle_pulses_then_clears_field() {
+        use super::*;
+
+        let mut regs = [0usize; 16];
+        let mut audio = CSR::<u32, pac::audio::Audio>::new(regs.as_mut_ptr() as *mut u32);
+        audio.wf(pac::audio::RX_CTL_ENABLE, 1);
+        audio.toggle(pac::audio::RX_CTL_RESET);
+        // After the pulse the reset bit is back to 0, and the unrelated
+        // ENABLE bit set just before is untouched.
+        assert_eq!(audio.rf(pac::audio::RX_CTL_RESET), 0);
+        assert_eq!(audio.rf(pac::audio::RX_CTL_ENABLE), 1);
+    }
+
+    #[test]
+    fn set_range_writes_a_multi_bit_range_without_disturbing_neighbors() {
+        use super::*;
+
+        let mut regs = [0usize; 16];
+        let mut audio = CSR::<u32, pac::audio::Audio>::new(regs.as_mut_ptr() as *mut u32);
+        audio.wf(pac::audio::RX_CTL_ENABLE, 1);
+        // Bits 4..=7 of RX_CTL aren't backed by a pre-declared Field here.
+        audio.set_range(pac::audio::RX_CTL, 4..=7, 0b1011);
+        assert_eq!(audio.r(pac::audio::RX_CTL) >> 4 & 0b1111, 0b1011);
+        // ENABLE, outside the range, is unaffected.
+        assert_eq!(audio.rf(pac::audio::RX_CTL_ENABLE), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "set_range: range must fit within a register-sized value")]
+    fn set_range_rejects_a_range_past_the_register_width() {
+        use super::*;
 
-        // This also compiles, despite the fact that the register offset is
-        // mismatched and nonsensical
-        audio.wf(pac::uart::TXFULL_TXFULL, 1);
+        let mut regs = [0usize; 16];
+        let mut audio = CSR::<u32, pac::audio::Audio>::new(regs.as_mut_ptr() as *mut u32);
+        audio.set_range(pac::audio::RX_CTL, 0..=(usize::BITS as usize), 0);
     }
 }